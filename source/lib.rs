@@ -2,7 +2,10 @@
 //!
 //! > Quick and dirty live reloading server for web development.
 //!
-//! This library provides one function [`watch`] that does the following:
+//! This library provides one function [`watch`] (and its siblings
+//! [`watch_with_command`] and [`watch_with_config`], for running a build
+//! command before reloading and for ignoring irrelevant changes) that does
+//! the following:
 //!
 //! * Creates a WebSocket server (with [`ws`]).
 //! * Creates a file watcher (with [`hotwatch`]) that detects file changes and
@@ -23,20 +26,34 @@
 
 #![feature(proc_macro_hygiene, decl_macro)]
 
-use std::{env, ffi::OsStr, fs::read, io::Cursor, path::PathBuf, thread};
+use std::{
+  env,
+  ffi::OsStr,
+  fs::{canonicalize, read, read_dir},
+  io::Cursor,
+  net::TcpListener,
+  path::{Path, PathBuf},
+  process::Command,
+  sync::{mpsc, Arc, Mutex},
+  thread,
+};
 
 #[macro_use]
 extern crate rocket;
 
 use anyhow::Result;
 use hotwatch::{notify::DebouncedEvent, Hotwatch};
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use rocket::{
+  config::Config,
   http::{ContentType, Status},
   response, Rocket, State,
 };
 
-/// The reload JavaScript that gets injected into HTML files so we can do
-/// `location.reload()` when the server detects changes.
+/// The reload JavaScript that gets injected into HTML files. It does a full
+/// `location.reload()` for most changes, but hot-swaps the affected
+/// `<link>` tag in place for stylesheet changes so scroll position and form
+/// state survive.
 pub(crate) const RELOAD_SCRIPT: &str = r#"<script>
 const socket = new WebSocket("ws://127.0.0.1:${WS_PORT}");
 
@@ -49,7 +66,19 @@ socket.addEventListener('open', (event) => {
 });
 
 socket.addEventListener('message', (event) => {
-  if (event.data === 'Reload') {
+  const message = JSON.parse(event.data);
+
+  if (message.kind === 'css') {
+    console.debug('Liver: hot-swapping', message.path);
+    document.querySelectorAll('link[rel="stylesheet"]').forEach((link) => {
+      const url = new URL(link.href);
+      if (url.pathname.endsWith(message.path)) {
+        const clone = link.cloneNode();
+        clone.href = `${url.origin}${url.pathname}?v=${Date.now()}`;
+        link.replaceWith(clone);
+      }
+    });
+  } else if (message.kind === 'reload') {
     console.debug('Liver: reloading...');
     location.reload();
   }
@@ -59,104 +88,328 @@ socket.addEventListener('message', (event) => {
 /// The default websocket, I picked 8001 as the default Rocket port is 8000.
 ///
 /// Both the Rocket and WS ports can be overridden with `ROCKET_PORT` and
-/// `WS_PORT` environment variables.
-pub(crate) const WS_PORT_DEFAULT: &str = "8001";
+/// `WS_PORT` environment variables, but if the chosen port is already taken
+/// [`get_available_port`] will fall back to an OS-assigned one instead of
+/// panicking.
+pub(crate) const WS_PORT_DEFAULT: u16 = 8001;
+
+/// The default Rocket port, see [`WS_PORT_DEFAULT`] for why 8001 was picked
+/// for the websocket server.
+pub(crate) const ROCKET_PORT_DEFAULT: u16 = 8000;
+
+/// Configuration for [`watch_with_config`], see the individual fields for
+/// info.
+#[derive(Debug, Default, Clone)]
+pub struct WatchConfig {
+  /// An optional shell command to run before broadcasting a reload, see
+  /// [`watch_with_command`].
+  pub command: Option<String>,
+
+  /// Additional gitignore-style glob patterns to ignore, on top of any
+  /// found in a `.liverignore` file at the watched root.
+  pub ignore: Vec<String>,
+}
 
 /// The watch function, see the [top-level module documentation](crate) for info.
 pub fn watch(path: &str) -> Result<()> {
+  watch_with_config(path, WatchConfig::default())
+}
+
+/// Like [`watch`], but runs `command` (e.g. a Sass/Tailwind/esbuild build
+/// step) through the shell before broadcasting a reload, every time a file
+/// changes. If `command` exits unsuccessfully the reload is skipped and its
+/// captured `stderr` is logged instead, so a build error doesn't get
+/// silently overwritten by stale output.
+pub fn watch_with_command(path: &str, command: Option<&str>) -> Result<()> {
+  watch_with_config(
+    path,
+    WatchConfig {
+      command: command.map(|command| command.to_string()),
+      ..WatchConfig::default()
+    },
+  )
+}
+
+/// Like [`watch`], but with full control over the build command and the
+/// ignore patterns used to skip irrelevant changes, see [`WatchConfig`].
+pub fn watch_with_config(path: &str, config: WatchConfig) -> Result<()> {
   let new_path = path.to_string();
+  let command = config.command;
+  let ignore = build_ignore_matcher(&new_path, &config.ignore);
+
+  let requested_ws_port = env::var("WS_PORT")
+    .ok()
+    .and_then(|port| port.parse().ok())
+    .unwrap_or(WS_PORT_DEFAULT);
+
+  // Used to report back the WS port that actually got bound, from inside
+  // the thread below, so the main thread can bake it into the reload
+  // script without widening the window described on `get_available_port`.
+  let (ws_port_tx, ws_port_rx) = mpsc::channel();
 
   // Use a separate thread to run the websocket server and file watcher in.
   thread::spawn(move || {
     let mut watcher = Hotwatch::new().unwrap();
 
-    // Start the websocket server.
-    ws::listen(ws_url(), move |out| {
-      // Then whenever we have a connection, start watching the source.
-      // I'm *pretty sure* this is fine, as far as I can tell Hotwatch just
-      // overrides any old watchers on the same path.
-      // I could be very wrong though!
-      watcher
-        .watch(&new_path, move |event| {
-          // Then, whenever Hotwatch notices an event, send the reload message.
-          if let DebouncedEvent::Write(_) = event {
-            out.send("Reload").unwrap();
+    // All currently connected clients, shared between the watcher callback
+    // below (which broadcasts to every one of them) and the websocket
+    // server (which adds and removes clients as they connect and
+    // disconnect).
+    let clients: Arc<Mutex<Vec<ws::Sender>>> = Arc::new(Mutex::new(Vec::new()));
+
+    // Start watching the source exactly once, before any connections come
+    // in, instead of re-arming on every `on_open` and hoping the latest
+    // watcher wins.
+    let watch_clients = Arc::clone(&clients);
+    let watch_root = new_path.clone();
+    watcher
+      .watch(&new_path, move |event| {
+        // Then, whenever Hotwatch notices an event, run the build command
+        // (if any) and broadcast the right message to every live client,
+        // dropping any that error.
+        if let DebouncedEvent::Write(path) = event {
+          if is_ignored(&ignore, &path) {
+            return;
           }
-        })
-        .unwrap();
-      |_| Ok(())
+
+          if let Some(command) = &command {
+            if !run_build_command(command) {
+              return;
+            }
+          }
+
+          let message = reload_message(&watch_root, &path);
+          let mut watch_clients = watch_clients.lock().unwrap();
+          watch_clients.retain(|client| client.send(message.clone()).is_ok());
+        }
+      })
+      .unwrap();
+
+    // Resolve the WS port right before binding to it, rather than long
+    // beforehand, to keep the window in which another process could steal
+    // it as small as this dependency allows, see `get_available_port`.
+    let ws_port = get_available_port(requested_ws_port);
+    ws_port_tx.send(ws_port).unwrap();
+
+    // Start the websocket server.
+    ws::listen(ws_url(ws_port), move |out| Client {
+      out,
+      clients: Arc::clone(&clients),
     })
     .unwrap();
   });
 
+  let ws_port = ws_port_rx.recv()?;
+
+  // Likewise, resolve the Rocket port right before building the config
+  // that's about to bind it.
+  let rocket_port = get_available_port(
+    env::var("ROCKET_PORT")
+      .ok()
+      .and_then(|port| port.parse().ok())
+      .unwrap_or(ROCKET_PORT_DEFAULT),
+  );
+  let config = Config::build(rocket::config::Environment::active()?)
+    .port(rocket_port)
+    .finalize()?;
+
   // Start Rocket, this will block the main thread.
-  Rocket::ignite()
+  Rocket::custom(config)
     .manage(path.to_string())
+    .manage(ws_port)
     .mount("/", routes![index, static_files])
     .launch();
 
   Ok(())
 }
 
+/// Attempts to bind a [`TcpListener`] on `127.0.0.1:<preferred>`, falling
+/// back to `127.0.0.1:0` (letting the OS hand back an ephemeral free port)
+/// if `preferred` is already taken. Returns whichever port was actually
+/// bound, or `preferred` itself if neither bind succeeds.
+///
+/// This is what lets several `liver` instances run simultaneously instead of
+/// panicking on a binding collision. Note this only *probes* availability:
+/// the listener is dropped (freeing the port again) before the caller binds
+/// it for real, so there's a small window where something else could grab
+/// it first. Neither `ws` nor Rocket let us hand them an already-bound
+/// listener to close that window entirely, so callers should call this as
+/// close as possible to where they actually bind, to keep the window small.
+pub(crate) fn get_available_port(preferred: u16) -> u16 {
+  TcpListener::bind(("127.0.0.1", preferred))
+    .or_else(|_| TcpListener::bind(("127.0.0.1", 0)))
+    .and_then(|listener| listener.local_addr())
+    .map(|address| address.port())
+    .unwrap_or(preferred)
+}
+
 /// Small convenience function to return the websocket URL.
-pub(crate) fn ws_url() -> String {
-  format!(
-    "127.0.0.1:{}",
-    env::var("WS_PORT").unwrap_or_else(|_| WS_PORT_DEFAULT.into())
-  )
+pub(crate) fn ws_url(port: u16) -> String {
+  format!("127.0.0.1:{}", port)
+}
+
+/// Builds the JSON message to broadcast for a changed `path`, relative to
+/// the watched `root`. Stylesheet changes get a `css` message so the client
+/// can hot-swap just that `<link>` instead of reloading the whole page;
+/// everything else falls back to a full `reload` message.
+///
+/// `root` and `path` are canonicalized before comparing, since `root` is
+/// typically the (possibly relative) string the caller passed into
+/// [`watch`], while notify backends (e.g. FSEvents on macOS) can report
+/// `path` already canonicalized to an absolute path. Stripping a relative
+/// root off an absolute path would otherwise fail and, previously, fell
+/// through to the raw, unstrippable path, producing a `css` message whose
+/// `path` could never match any `<link>` in the injected client script. If
+/// `root` still can't be stripped off `path` after canonicalizing, fall
+/// back to a full `reload` instead of emitting an unmatchable `css` message.
+pub(crate) fn reload_message(root: &str, path: &Path) -> String {
+  if path.extension().and_then(OsStr::to_str) == Some("css") {
+    let canonical_root = canonicalize(root).unwrap_or_else(|_| PathBuf::from(root));
+    let canonical_path = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+    if let Ok(relative) = canonical_path.strip_prefix(&canonical_root) {
+      let relative = relative.to_string_lossy().replace('\\', "/");
+
+      return format!(r#"{{"kind":"css","path":"/{}"}}"#, relative);
+    }
+  }
+
+  r#"{"kind":"reload"}"#.to_string()
+}
+
+/// Builds the matcher used to skip irrelevant file changes: reads a
+/// `.liverignore` file (gitignore-style globs) from `root` if one exists,
+/// and layers the extra `patterns` on top of it.
+///
+/// `root` is canonicalized before being handed to [`GitignoreBuilder`], to
+/// match against [`is_ignored`], which canonicalizes the path it's checking
+/// the same way. Otherwise a relative `root` (e.g. `"tests/"`) wouldn't
+/// share a prefix with the canonicalized, absolute paths some notify
+/// backends report, and every pattern would silently stop matching.
+pub(crate) fn build_ignore_matcher(root: &str, patterns: &[String]) -> Gitignore {
+  let canonical_root = canonicalize(root).unwrap_or_else(|_| PathBuf::from(root));
+  let mut builder = GitignoreBuilder::new(&canonical_root);
+
+  builder.add(canonical_root.join(".liverignore"));
+
+  for pattern in patterns {
+    // Ignore invalid patterns rather than failing the whole watch, this
+    // stays in the spirit of the "quick and dirty" approach of the rest
+    // of the library.
+    let _ = builder.add_line(None, pattern);
+  }
+
+  builder.build().unwrap_or_else(|_| Gitignore::empty())
+}
+
+/// Checks whether `path` should be ignored according to `ignore`,
+/// canonicalizing it first so it's comparable with the canonicalized root
+/// [`build_ignore_matcher`] built the matcher from.
+pub(crate) fn is_ignored(ignore: &Gitignore, path: &Path) -> bool {
+  let canonical_path = canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+
+  // Like git, a file inside an ignored directory is ignored too. This check
+  // only works (and only doesn't panic) for paths under the matcher's root.
+  if canonical_path.starts_with(ignore.path()) {
+    return ignore
+      .matched_path_or_any_parents(&canonical_path, canonical_path.is_dir())
+      .is_ignore();
+  }
+
+  ignore
+    .matched(&canonical_path, canonical_path.is_dir())
+    .is_ignore()
+}
+
+/// Runs `command` through the platform shell and waits for it to finish.
+/// Returns whether it exited successfully; on failure its captured `stderr`
+/// is printed so the developer sees the build error instead of a silent
+/// reload of stale output.
+pub(crate) fn run_build_command(command: &str) -> bool {
+  let shell = if cfg!(windows) { "cmd" } else { "sh" };
+  let flag = if cfg!(windows) { "/C" } else { "-c" };
+
+  match Command::new(shell).arg(flag).arg(command).output() {
+    Ok(output) if output.status.success() => true,
+    Ok(output) => {
+      eprintln!(
+        "Liver: build command failed:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+      );
+
+      false
+    }
+    Err(error) => {
+      eprintln!("Liver: failed to run build command: {}", error);
+
+      false
+    }
+  }
+}
+
+/// A single connected websocket client, responsible for adding and removing
+/// its own [`ws::Sender`] from the shared `clients` list so the file watcher
+/// can broadcast reloads to everyone instead of just the most recently
+/// connected tab.
+pub(crate) struct Client {
+  out: ws::Sender,
+  clients: Arc<Mutex<Vec<ws::Sender>>>,
+}
+
+impl ws::Handler for Client {
+  fn on_open(&mut self, _: ws::Handshake) -> ws::Result<()> {
+    self.clients.lock().unwrap().push(self.out.clone());
+
+    Ok(())
+  }
+
+  fn on_close(&mut self, _: ws::CloseCode, _: &str) {
+    self
+      .clients
+      .lock()
+      .unwrap()
+      .retain(|client| client.token() != self.out.token());
+  }
 }
 
 /// The regular index needs to be handled specifically, it just relays to
 /// `static_files` though. *shrug*
 #[get("/")]
-pub(crate) fn index<'r>(source: State<String>) -> response::Result<'r> {
-  static_files(None, source)
+pub(crate) fn index<'r>(
+  source: State<String>,
+  ws_port: State<u16>,
+) -> response::Result<'r> {
+  static_files(None, source, ws_port)
 }
 
 #[get("/<path..>")]
 pub(crate) fn static_files<'r>(
   path: Option<PathBuf>,
   source: State<String>,
+  ws_port: State<u16>,
 ) -> response::Result<'r> {
-  // Grab the reload JavaScript and set the WS_PORT in it.
+  // Grab the reload JavaScript and set the WS_PORT to the port we actually
+  // bound, rather than whatever was merely requested.
   let mut reload_script = RELOAD_SCRIPT
-    .replace(
-      "${WS_PORT}",
-      &env::var("WS_PORT").unwrap_or_else(|_| WS_PORT_DEFAULT.to_string()),
-    )
+    .replace("${WS_PORT}", &ws_port.to_string())
     .as_bytes()
     .to_vec();
 
-  if path.is_none() {
-    // If `path` is None that means it was called from `index`, so we just return
-    // the `index.html` at the `source` root or a 404 if it doesn't exist.
-    let path = PathBuf::from(source.inner()).join("index.html");
-
-    if let Ok(mut file) = read(&path) {
-      // Insert the reload JavaScript since we're going to be returning HTML.
-      file.append(&mut reload_script);
-
-      return response::Response::build()
-        .header(ContentType::HTML)
-        .sized_body(Cursor::new(file))
-        .ok();
-    } else {
-      return Err(Status::NotFound);
-    }
-  }
-
-  // Join our `source` path with the URL `path` so we get the correct
-  // relative URL.
-  let mut path = PathBuf::from(source.inner()).join(path.unwrap());
+  // `path` is None when called from `index`, treat that the same as a
+  // request for the `source` root.
+  let request_path = path.unwrap_or_default();
+  let source_root = PathBuf::from(source.inner());
+  let mut full_path = source_root.join(&request_path);
 
-  // If it's pointing to a directory then join `index.html`.
-  if path.is_dir() {
-    path = path.join("index.html");
+  // If it's pointing to a directory then look for an `index.html` in it.
+  let requested_dir = full_path.is_dir();
+  if requested_dir {
+    full_path = full_path.join("index.html");
   }
 
-  if let Ok(mut file) = read(&path) {
+  if let Ok(mut file) = read(&full_path) {
     // Get the extension of the file, if any.
-    let file_extension = path.extension().and_then(OsStr::to_str).unwrap_or("");
+    let file_extension = full_path.extension().and_then(OsStr::to_str).unwrap_or("");
 
     // Get the content type and use plaintext if we can't find it.
     let content_type =
@@ -171,7 +424,195 @@ pub(crate) fn static_files<'r>(
       .header(content_type)
       .sized_body(Cursor::new(file))
       .ok()
+  } else if requested_dir {
+    // No `index.html` in this directory, fall back to a generated listing.
+    let listing = directory_listing(&source_root, &request_path, reload_script);
+
+    response::Response::build()
+      .header(ContentType::HTML)
+      .sized_body(Cursor::new(listing))
+      .ok()
+  } else {
+    not_found(&source_root, reload_script)
+  }
+}
+
+/// Escapes `&`, `<`, `>`, and `"` so `value` is safe to drop into HTML text
+/// or a quoted attribute. Filenames aren't trustworthy input on Linux or
+/// macOS (e.g. `Q&A.txt` or `<script>.txt` are both valid), so entry names
+/// going into [`directory_listing`] need this before they're interpolated.
+pub(crate) fn escape_html(value: &str) -> String {
+  value
+    .replace('&', "&amp;")
+    .replace('<', "&lt;")
+    .replace('>', "&gt;")
+    .replace('"', "&quot;")
+}
+
+/// Percent-encodes a single path segment for safe use inside an `href`.
+/// Deliberately minimal (an allow-list of unreserved characters) rather
+/// than pulling in a whole URL-encoding crate, in keeping with the rest of
+/// the library.
+pub(crate) fn percent_encode_segment(value: &str) -> String {
+  value
+    .bytes()
+    .map(|byte| match byte {
+      b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+        (byte as char).to_string()
+      }
+      _ => format!("%{:02X}", byte),
+    })
+    .collect()
+}
+
+/// Generates an HTML directory listing for `request_path` (relative to
+/// `source_root`), linking every entry by its path from the site root, and
+/// injects the reload script so the listing itself live-reloads.
+pub(crate) fn directory_listing(
+  source_root: &PathBuf,
+  request_path: &PathBuf,
+  mut reload_script: Vec<u8>,
+) -> Vec<u8> {
+  // `request_path` is a directory name taken straight off disk, it's just
+  // as untrustworthy as the entry `name`s below, so it gets the same
+  // treatment: escaped for display, percent-encoded per segment for hrefs.
+  let url_path = format!("/{}", request_path.to_string_lossy().replace('\\', "/"));
+  let href_prefix: String = request_path
+    .components()
+    .map(|component| percent_encode_segment(&component.as_os_str().to_string_lossy()))
+    .fold(String::new(), |mut prefix, segment| {
+      prefix.push('/');
+      prefix.push_str(&segment);
+      prefix
+    });
+
+  let mut entries = read_dir(source_root.join(request_path))
+    .map(|entries| {
+      entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .collect::<Vec<_>>()
+    })
+    .unwrap_or_default();
+  entries.sort();
+
+  let links = entries
+    .iter()
+    .map(|name| {
+      format!(
+        r#"<li><a href="{}/{}">{}</a></li>"#,
+        href_prefix,
+        percent_encode_segment(name),
+        escape_html(name)
+      )
+    })
+    .collect::<String>();
+
+  let mut html = format!(
+    "<!DOCTYPE html><html><head><title>Index of {0}</title></head><body>\
+     <h1>Index of {0}</h1><ul>{1}</ul></body></html>",
+    escape_html(&url_path),
+    links
+  )
+  .into_bytes();
+
+  html.append(&mut reload_script);
+
+  html
+}
+
+/// Serves a user-provided `404.html` from `source_root` (with the reload
+/// script injected) if one exists, falling back to a bare
+/// [`Status::NotFound`] otherwise.
+pub(crate) fn not_found<'r>(
+  source_root: &PathBuf,
+  mut reload_script: Vec<u8>,
+) -> response::Result<'r> {
+  let path = source_root.join("404.html");
+
+  if let Ok(mut file) = read(&path) {
+    file.append(&mut reload_script);
+
+    response::Response::build()
+      .status(Status::NotFound)
+      .header(ContentType::HTML)
+      .sized_body(Cursor::new(file))
+      .ok()
   } else {
     Err(Status::NotFound)
   }
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn reload_message_css_change_with_matching_root() {
+    let message = reload_message("/tmp/project", Path::new("/tmp/project/styles/main.css"));
+
+    assert_eq!(message, r#"{"kind":"css","path":"/styles/main.css"}"#);
+  }
+
+  #[test]
+  fn reload_message_falls_back_to_reload_when_root_cannot_be_stripped() {
+    // Simulates a watcher backend reporting a canonicalized absolute path
+    // that doesn't share a prefix with the (possibly relative,
+    // non-canonical) `root` the caller passed into `watch`.
+    let message = reload_message("tests", Path::new("/unrelated/absolute/path/style.css"));
+
+    assert_eq!(message, r#"{"kind":"reload"}"#);
+  }
+
+  #[test]
+  fn reload_message_non_css_is_always_reload() {
+    let message = reload_message("/tmp/project", Path::new("/tmp/project/index.html"));
+
+    assert_eq!(message, r#"{"kind":"reload"}"#);
+  }
+
+  #[test]
+  fn ignore_matcher_matches_relative_root_against_canonicalized_path() {
+    // `"."` mirrors a caller passing a relative root into `watch`/
+    // `watch_with_config` (e.g. `"tests/"`), while the path being checked
+    // is already canonicalized and absolute, as some notify backends
+    // report it.
+    let ignore = build_ignore_matcher(".", &["*.log".to_string()]);
+    let root = canonicalize(".").unwrap();
+
+    assert!(is_ignored(&ignore, &root.join("debug.log")));
+    assert!(!is_ignored(&ignore, &root.join("style.css")));
+  }
+
+  #[test]
+  fn escape_html_escapes_special_characters() {
+    assert_eq!(
+      escape_html(r#"<script>Q&A"</script>"#),
+      "&lt;script&gt;Q&amp;A&quot;&lt;/script&gt;"
+    );
+  }
+
+  #[test]
+  fn percent_encode_segment_encodes_reserved_characters() {
+    assert_eq!(percent_encode_segment("notes#1.md"), "notes%231.md");
+    assert_eq!(percent_encode_segment(r#"Q&A.txt"#), "Q%26A.txt");
+    assert_eq!(percent_encode_segment("safe-name_1.0~x"), "safe-name_1.0~x");
+  }
+
+  #[test]
+  fn directory_listing_escapes_malicious_request_path() {
+    // A directory whose on-disk name is itself a markup-breaking payload
+    // must not appear unescaped in the listing, neither in the title/h1
+    // text nor in any entry's `href`.
+    let malicious = r#""><script>alert(document.domain)</script>"#;
+    let html = directory_listing(
+      &PathBuf::from("/tmp/liver-nonexistent-root"),
+      &PathBuf::from(malicious),
+      Vec::new(),
+    );
+    let html = String::from_utf8(html).unwrap();
+
+    assert!(!html.contains(malicious));
+    assert!(!html.contains("<script>"));
+  }
+}